@@ -1,7 +1,11 @@
 mod play_queue;
 
 use std::error::Error;
+use std::mem;
 use std::sync;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic;
+use std::time::Duration;
 
 use glib;
 use gstreamer as gst;
@@ -29,7 +33,7 @@ pub fn init_audio_subsystem() -> Result<(), String> {
     })
 }
 
-#[derive(PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 enum StreamState {
     Playing,
     Paused,
@@ -37,7 +41,19 @@ enum StreamState {
 }
 
 pub enum StreamEvent {
+    /// The `about-to-finish` signal fired with no gapless replacement
+    /// available, so the stream is about to end.
     Completed,
+    /// The pipeline moved from one state to another.
+    StateChanged { old: StreamState, new: StreamState },
+    /// End-of-stream was reached.
+    Eos,
+    /// An error was reported on the pipeline's message bus.
+    Error { message: String },
+    /// Buffering progress, as a percentage between 0 and 100.
+    Buffering(i32),
+    /// A tag (metadata) message was received.
+    Tag(gst::TagList),
 }
 
 /// Represents a behaviour that can be used to stream data of any type
@@ -53,8 +69,28 @@ trait Streamable {
     fn pause(&self);
     /// Return the state that the stream is currently in.
     fn state(&self) -> StreamState;
+    /// Set the playback volume, as a linear gain between `0.0` and `1.0`.
+    fn set_volume(&self, volume: f64);
+    /// Return the current playback volume.
+    fn get_volume(&self) -> f64;
+    /// Mute or unmute the stream without changing its volume level.
+    fn set_mute(&self, mute: bool);
+    /// Return the current playback position, if it is known.
+    fn position(&self) -> Option<gst::ClockTime>;
+    /// Return the total duration of the current stream, if it is known.
+    fn duration(&self) -> Option<gst::ClockTime>;
+    /// Seek to the given position in the stream.
+    fn seek(&self, to: gst::ClockTime);
     /// Return a receiver for stream events.
     fn event_listener(&self) -> sync::mpsc::Receiver<StreamEvent>;
+    /// Register a callback invoked when the stream is about to finish,
+    /// giving it the chance to supply the URI of the next item to queue.
+    ///
+    /// Returning `Some(uri)` swaps the stream source in place, without a
+    /// state change, for gapless playback. Returning `None` leaves the
+    /// stream to finish normally and emit `StreamEvent::Completed`.
+    fn set_next_uri_provider<F>(&self, provider: F)
+        where F: Fn() -> Option<String> + Send + 'static;
 }
 
 impl From<gst::State> for StreamState {
@@ -74,26 +110,167 @@ impl From<gst::State> for StreamState {
 /// files.
 #[derive(Clone)]
 struct AudioStreamer {
-    // TODO: ensure that this memory isn't copied
-    playbin: Box<gst::Element>,
+    // Behind a lock so `promote()` can swap in a preloaded element and
+    // have every other method keep working against whatever is current.
+    playbin: Arc<Mutex<gst::Element>>,
+    next_uri_provider: Arc<Mutex<Option<Box<Fn() -> Option<String> + Send>>>>,
+    // Updated by `change_state` and from the bus watch's `StateChanged`
+    // messages, so `state()` is a cheap read instead of a blocking
+    // `get_state` query.
+    state: Arc<Mutex<StreamState>>,
+    // The sender handed out by `event_listener`, kept so `promote()` can
+    // re-wire the `about-to-finish` signal and bus watch on to the new
+    // playbin. `None` until `event_listener` has been called.
+    event_tx: Arc<Mutex<Option<sync::mpsc::Sender<StreamEvent>>>>,
+    // The signal handler and bus watch currently wired on to `playbin`,
+    // so `promote()` can tear them down before re-wiring on to the
+    // newly promoted element instead of leaking them on the old one.
+    wiring: Arc<Mutex<Option<(glib::SignalHandlerId, glib::SourceId)>>>,
 }
 
 impl AudioStreamer {
     fn new() -> AudioStreamer {
         AudioStreamer {
-            playbin: Box::new(gst::ElementFactory::make("playbin", None).unwrap())
+            playbin: Arc::new(Mutex::new(gst::ElementFactory::make("playbin", None).unwrap())),
+            next_uri_provider: Arc::new(Mutex::new(None)),
+            state: Arc::new(Mutex::new(StreamState::Stopped)),
+            event_tx: Arc::new(Mutex::new(None)),
+            wiring: Arc::new(Mutex::new(None)),
         }
     }
 
     fn change_state(&self, state: gst::State) {
-        let new_state = self.playbin.set_state(state);
+        let new_state = self.playbin.lock().unwrap().set_state(state);
         assert_ne!(new_state, gst::StateChangeReturn::Failure);
+        // The bus watch only runs between idle-loop ticks, and the bus is
+        // flushed on the way to `Null`, so record the requested state now.
+        *self.state.lock().unwrap() = StreamState::from(state);
+    }
+
+    /// Start buffering `uri` on a secondary element so it's ready to
+    /// play the instant it's promoted, rather than connecting cold.
+    fn preload(&self, uri: String) -> Preloaded {
+        let element: gst::Element = gst::ElementFactory::make("playbin", None).unwrap();
+        element.set_property("uri", &glib::Value::from(&uri)).unwrap();
+        element.set_state(gst::State::Paused);
+
+        Preloaded { element }
+    }
+
+    /// Make a previously preloaded source the active stream: it becomes
+    /// `playbin`, and the old element is torn down. The `about-to-finish`
+    /// signal and bus watch are unwired from the old element and
+    /// re-wired on to the new one so `state()` and gapless transitions
+    /// keep working against it, instead of leaking the old element and
+    /// its watch forever.
+    fn promote(&self, preloaded: Preloaded) {
+        let old = {
+            let mut playbin = self.playbin.lock().unwrap();
+            mem::replace(&mut *playbin, preloaded.element)
+        };
+
+        self.unwire_signals(&old);
+        old.set_state(gst::State::Null);
+        self.wire_signals();
+    }
+
+    /// Disconnect the `about-to-finish` handler and remove the bus
+    /// watch previously wired by `wire_signals`, if any.
+    fn unwire_signals(&self, playbin: &gst::Element) {
+        if let Some((handler_id, watch_id)) = self.wiring.lock().unwrap().take() {
+            playbin.disconnect(handler_id);
+            glib::source_remove(watch_id);
+        }
+    }
+
+    /// (Re-)connect the `about-to-finish` signal and message bus watch
+    /// on to the current `playbin`, forwarding to whichever sender
+    /// `event_listener` registered. A no-op before `event_listener` has
+    /// been called for the first time.
+    fn wire_signals(&self) {
+        let tx = match self.event_tx.lock().unwrap().clone() {
+            Some(tx) => tx,
+            None => return,
+        };
+
+        let playbin = self.playbin.lock().unwrap().clone();
+        let next_uri_provider = self.next_uri_provider.clone();
+
+        let finish_playbin = playbin.clone();
+        let finish_tx = tx.clone();
+
+        let handler_id = playbin.connect("about-to-finish", false, move |_| {
+            let provider = next_uri_provider.lock().unwrap();
+            let next_uri = provider.as_ref().and_then(|f| f());
+
+            if let Some(uri) = next_uri {
+                // Swap the source in place so playbin keeps streaming
+                // without tearing the pipeline down, giving a gapless
+                // transition between tracks.
+                finish_playbin.set_property("uri", &glib::Value::from(&uri)).unwrap();
+            } else {
+                finish_tx.send(StreamEvent::Completed).unwrap();
+            }
+
+            None
+        }).unwrap();
+
+        let bus = playbin.get_bus().unwrap();
+        let state = self.state.clone();
+        // Compared against `StateChanged` messages' source below so that
+        // a child element (decodebin, a sink, ...) transitioning through
+        // its own states doesn't stomp on our cached top-level state.
+        let playbin_obj = playbin.clone().upcast::<gst::Object>();
+
+        let watch_id = bus.add_watch(move |_, message| {
+            use gstreamer::MessageView;
+
+            match message.view() {
+                MessageView::StateChanged(changed) => {
+                    let from_playbin = message
+                        .get_src()
+                        .map_or(false, |src| src == playbin_obj);
+
+                    if from_playbin {
+                        let old = StreamState::from(changed.get_old());
+                        let new = StreamState::from(changed.get_current());
+                        *state.lock().unwrap() = new;
+                        let _ = tx.send(StreamEvent::StateChanged { old, new });
+                    }
+                }
+                MessageView::Eos(..) => {
+                    let _ = tx.send(StreamEvent::Eos);
+                }
+                MessageView::Error(err) => {
+                    let _ = tx.send(StreamEvent::Error {
+                        message: err.get_error().description().to_owned(),
+                    });
+                }
+                MessageView::Buffering(buffering) => {
+                    let _ = tx.send(StreamEvent::Buffering(buffering.get_percent()));
+                }
+                MessageView::Tag(tag) => {
+                    let _ = tx.send(StreamEvent::Tag(tag.get_tags()));
+                }
+                _ => (),
+            }
+
+            glib::Continue(true)
+        });
+
+        *self.wiring.lock().unwrap() = Some((handler_id, watch_id));
     }
 }
 
+/// A secondary source buffering ahead of the swap, produced by
+/// `AudioStreamer::preload`.
+struct Preloaded {
+    element: gst::Element,
+}
+
 impl Streamable for AudioStreamer {
     fn queue(&self, uri: String) {
-        self.playbin.set_property("uri", &glib::Value::from(&uri)).unwrap();
+        self.playbin.lock().unwrap().set_property("uri", &glib::Value::from(&uri)).unwrap();
     }
 
     fn start(&self) {
@@ -109,33 +286,118 @@ impl Streamable for AudioStreamer {
     }
 
     fn state(&self) -> StreamState {
-        // FIXME: Could block UI thread
-        let (_, current_state, _) = self.playbin.get_state(gst::CLOCK_TIME_NONE);
-        StreamState::from(current_state)
+        *self.state.lock().unwrap()
+    }
+
+    fn set_volume(&self, volume: f64) {
+        let volume = volume.max(0.0).min(1.0);
+        self.playbin.lock().unwrap().set_property("volume", &glib::Value::from(&volume)).unwrap();
+    }
+
+    fn get_volume(&self) -> f64 {
+        self.playbin.lock().unwrap().get_property("volume").unwrap().get().unwrap()
+    }
+
+    fn set_mute(&self, mute: bool) {
+        self.playbin.lock().unwrap().set_property("mute", &glib::Value::from(&mute)).unwrap();
+    }
+
+    fn position(&self) -> Option<gst::ClockTime> {
+        self.playbin.lock().unwrap().query_position::<gst::ClockTime>()
+    }
+
+    fn duration(&self) -> Option<gst::ClockTime> {
+        self.playbin.lock().unwrap().query_duration::<gst::ClockTime>()
+    }
+
+    fn seek(&self, to: gst::ClockTime) {
+        // Seeking while stopped has nothing to act on; while paused,
+        // GStreamer applies the seek and takes effect once play resumes.
+        if self.state() == StreamState::Stopped {
+            return;
+        }
+
+        self.playbin.lock().unwrap()
+            .seek_simple(gst::SeekFlags::FLUSH | gst::SeekFlags::KEY_UNIT, to).unwrap();
     }
 
     fn event_listener(&self) -> sync::mpsc::Receiver<StreamEvent> {
         let (tx, rx) = sync::mpsc::channel();
-        let tx_mutex = sync::Mutex::new(tx);
-
-        self.playbin.connect("about-to-finish", false, move |_| {
-            let tx = tx_mutex.lock().unwrap();
-            tx.send(StreamEvent::Completed).unwrap();
-            None
-        }).unwrap();
+        *self.event_tx.lock().unwrap() = Some(tx);
+        self.wire_signals();
 
         rx
     }
+
+    fn set_next_uri_provider<F>(&self, provider: F)
+        where F: Fn() -> Option<String> + Send + 'static
+    {
+        *self.next_uri_provider.lock().unwrap() = Some(Box::new(provider));
+    }
 }
 
 #[derive(Clone)]
 pub struct Player {
     pub is_looping: bool,
-    play_queue: PlayQueue<Track>,
+    /// When enabled, the next track is swapped in to `playbin` directly
+    /// from the `about-to-finish` signal handler instead of tearing
+    /// down and rebuilding the pipeline, giving gapless transitions.
+    pub gapless: bool,
+    // Shared with the streamer's `about-to-finish` handler, which runs
+    // off the glib idle loop and needs to advance the queue in lockstep.
+    play_queue: Arc<Mutex<PlayQueue<Track>>>,
+    // Listeners registered via `subscribe()`. Shared so the gapless
+    // `about-to-finish` handler can fan out events too.
+    subscribers: Arc<Mutex<Vec<sync::mpsc::Sender<PlayerEvent>>>>,
+    /// How long before the current track ends to start preloading the
+    /// next one in the queue.
+    pub preload_before_end: Duration,
+    // Source preloaded for the upcoming track, tagged with its id so a
+    // queue change before the swap can be detected and the stale
+    // preload discarded instead of wrongly promoted.
+    preloaded: Arc<Mutex<Option<(u64, Preloaded)>>>,
+    // Set by the gapless `about-to-finish` handler when it already
+    // advanced the queue, found it exhausted and announced
+    // `TrackFinished`/`EndOfQueue` itself. The fallback `Completed`
+    // handling checks this so it doesn't re-run the same queue logic
+    // and emit those events a second time.
+    gapless_end_of_queue: Arc<atomic::AtomicBool>,
     // TODO; use generics
     streamer: AudioStreamer,
 }
 
+/// A high-level playback event, richer than the stream-level
+/// `StreamEvent`s, for things like a now-playing widget or a scrobbler
+/// to react to.
+#[derive(Debug, Clone)]
+pub enum PlayerEvent {
+    /// Playback of a track has started.
+    TrackStarted(Track),
+    /// Playback of a track has finished, either naturally or by
+    /// skipping to another track.
+    TrackFinished(Track),
+    /// Playback has been paused.
+    Paused,
+    /// Playback has been stopped.
+    Stopped,
+    /// A track was added to the play queue.
+    QueueChanged,
+    /// The play queue was exhausted and `is_looping` is disabled.
+    EndOfQueue,
+}
+
+/// Send `event` to every live subscriber, dropping any whose receiving
+/// end has been closed.
+fn emit(subscribers: &Mutex<Vec<sync::mpsc::Sender<PlayerEvent>>>, event: PlayerEvent) {
+    let mut subscribers = subscribers.lock().unwrap();
+    subscribers.retain(|tx| tx.send(event.clone()).is_ok());
+}
+
+fn duration_to_clock_time(duration: Duration) -> gst::ClockTime {
+    let nanos = duration.as_secs() * 1_000_000_000 + duration.subsec_nanos() as u64;
+    gst::ClockTime::from_nseconds(nanos)
+}
+
 /// A command to be passed to the media player.
 #[derive(Debug)]
 pub enum PlayerCommand {
@@ -151,6 +413,13 @@ pub enum PlayerCommand {
     Next,
     /// Move to the previous track in the queue.
     Previous,
+    /// Set the playback volume, as a linear gain between `0.0` and `1.0`.
+    /// Values outside this range are clamped.
+    SetVolume(f64),
+    /// Mute or unmute playback without changing the volume level.
+    Mute(bool),
+    /// Seek to the given position in the current track.
+    Seek(gst::ClockTime),
     /// Kill the audio player.
     Kill,
 }
@@ -162,16 +431,75 @@ impl Player {
     pub fn new() -> Player {
         Player {
             is_looping: false,
-            play_queue: PlayQueue::new(),
+            gapless: false,
+            play_queue: Arc::new(Mutex::new(PlayQueue::new())),
+            subscribers: Arc::new(Mutex::new(Vec::new())),
+            preload_before_end: Duration::from_secs(30),
+            preloaded: Arc::new(Mutex::new(None)),
+            gapless_end_of_queue: Arc::new(atomic::AtomicBool::new(false)),
             streamer: AudioStreamer::new(),
         }
     }
 
+    /// Register a new listener for playback events.
+    ///
+    /// Must be called before `event_listener()`, which consumes the
+    /// player. Multiple subscribers (a UI widget, a scrobbler, a
+    /// now-playing notifier, ...) can each hold their own receiver.
+    pub fn subscribe(&self) -> sync::mpsc::Receiver<PlayerEvent> {
+        let (tx, rx) = sync::mpsc::channel();
+        self.subscribers.lock().unwrap().push(tx);
+        rx
+    }
+
     /// Create an event listener that receives messages over
     /// a channel and performs the relevant command.
     pub fn event_listener(mut self) -> PlayerSender {
         let (tx, rx) = sync::mpsc::channel();
 
+        if self.gapless {
+            let play_queue = self.play_queue.clone();
+            let is_looping = self.is_looping;
+            let subscribers = self.subscribers.clone();
+            let gapless_end_of_queue = self.gapless_end_of_queue.clone();
+
+            self.streamer.set_next_uri_provider(move || {
+                let mut play_queue = play_queue.lock().unwrap();
+                let finished = play_queue.current().cloned();
+
+                if play_queue.next().is_none() {
+                    if is_looping {
+                        play_queue.reset();
+                    } else {
+                        if let Some(track) = finished {
+                            emit(&subscribers, PlayerEvent::TrackFinished(track));
+                        }
+                        emit(&subscribers, PlayerEvent::EndOfQueue);
+                        // The fallback `Completed` handling still fires
+                        // from the `about-to-finish` signal below (it
+                        // only special-cases `Some(uri)`); flag that the
+                        // queue-exhaustion events above already ran so
+                        // it just stops playback instead of redoing them.
+                        gapless_end_of_queue.store(true, atomic::Ordering::SeqCst);
+                        return None;
+                    }
+                }
+
+                if let Some(track) = finished {
+                    emit(&subscribers, PlayerEvent::TrackFinished(track));
+                }
+
+                let next = play_queue.current().cloned();
+                if let Some(ref track) = next {
+                    emit(&subscribers, PlayerEvent::TrackStarted(track.clone()));
+                }
+
+                next.map(|track| track.file)
+            });
+        }
+
+        let stream_rx = self.streamer.event_listener();
+
         // Add runner in to glib event loop
         glib::idle_add(move || {
             let mut should_continue = true;
@@ -187,32 +515,112 @@ impl Player {
                     Stop  => self.stop(),
                     Next  => self.next_track(),
                     Previous => self.previous_track(),
+                    SetVolume(volume) => self.streamer.set_volume(volume),
+                    Mute(mute) => self.streamer.set_mute(mute),
+                    Seek(to) => self.streamer.seek(to),
                     Kill => should_continue = false,
                 }
             }
 
+            if let Ok(event) = stream_rx.try_recv() {
+                use self::StreamEvent::*;
+                match event {
+                    // The current track finished without the gapless
+                    // path swapping in a replacement (gapless disabled,
+                    // or the queue was exhausted) — fall back to a hard
+                    // stop/start, unless the gapless path already
+                    // handled the exhaustion and just needs a stop.
+                    Completed => {
+                        if self.gapless_end_of_queue.swap(false, atomic::Ordering::SeqCst) {
+                            self.stop();
+                        } else {
+                            self.next_track();
+                        }
+                    }
+                    Error { message } => error!("Stream error: {}", message),
+                    StateChanged { .. } | Eos | Buffering(_) | Tag(_) => (),
+                }
+            }
+
+            self.maybe_preload();
+
             glib::Continue(should_continue)
         });
 
         tx
     }
 
+    /// If the current track is within `preload_before_end` of finishing,
+    /// start buffering the next queued track so it's ready to swap in
+    /// instantly instead of stalling on a cold connection.
+    fn maybe_preload(&self) {
+        // Gapless mode already swaps the next track's uri in place from
+        // `about-to-finish`, so a secondary preloaded element here would
+        // just be a redundant pipeline left to leak.
+        if self.gapless {
+            return;
+        }
+
+        if !self.is_playing() {
+            return;
+        }
+
+        let (position, duration) = match self.progress() {
+            (Some(position), Some(duration)) => (position, duration),
+            _ => return,
+        };
+
+        let threshold = duration_to_clock_time(self.preload_before_end);
+
+        if duration < position || duration - position > threshold {
+            return;
+        }
+
+        let next_track = self.play_queue.lock().unwrap().peek_next().cloned();
+        let next_track = match next_track {
+            Some(track) => track,
+            None => return,
+        };
+
+        let mut preloaded = self.preloaded.lock().unwrap();
+        let already_preloading = match preloaded.as_ref() {
+            Some(&(id, _)) => id == next_track.id,
+            None => false,
+        };
+
+        if !already_preloading {
+            *preloaded = Some((next_track.id, self.streamer.preload(next_track.file.clone())));
+        }
+    }
+
     /// Returns `true` if the player is currently streaming audio.
-    ///
-    /// WARNING: May block the UI thread.
     fn is_playing(&self) -> bool {
         self.streamer.state() == StreamState::Playing
     }
 
+    /// Returns the current playback position and the total duration of
+    /// the current track, for drawing a progress bar. Either may be
+    /// `None` if GStreamer cannot answer the query yet.
+    pub fn progress(&self) -> (Option<gst::ClockTime>, Option<gst::ClockTime>) {
+        (self.streamer.position(), self.streamer.duration())
+    }
+
+    /// Returns the current playback volume, as a linear gain between
+    /// `0.0` and `1.0`, for initializing or syncing a volume slider.
+    pub fn get_volume(&self) -> f64 {
+        self.streamer.get_volume()
+    }
+
     /// Returns the current track in the queue. May or may not
     /// be currently playing.
-    fn current_track(&self) -> Option<&Track> {
-        self.play_queue.current()
+    fn current_track(&self) -> Option<Track> {
+        self.play_queue.lock().unwrap().current().cloned()
     }
 
     /// Add a track to the end of the play queue.
     fn queue(&mut self, track: &Track) {
-        self.play_queue.append(track);
+        self.play_queue.lock().unwrap().append(track);
+        emit(&self.subscribers, PlayerEvent::QueueChanged);
     }
 
     /// Begin playback of the current track.
@@ -222,9 +630,27 @@ impl Player {
         assert!(self.streamer.state() != StreamState::Playing);
 
         if let Some(track) = self.current_track() {
-            // TODO: investigate clone
-            self.streamer.queue(track.file.clone());
+            let preloaded = self.preloaded.lock().unwrap().take();
+
+            match preloaded {
+                Some((id, source)) if id == track.id => {
+                    self.streamer.promote(source);
+                }
+                Some((_, stale)) => {
+                    // The queue moved on before the preloaded track
+                    // came up; drop it and connect cold instead.
+                    stale.element.set_state(gst::State::Null);
+                    // TODO: investigate clone
+                    self.streamer.queue(track.file.clone());
+                }
+                None => {
+                    // TODO: investigate clone
+                    self.streamer.queue(track.file.clone());
+                }
+            }
+
             self.streamer.start();
+            emit(&self.subscribers, PlayerEvent::TrackStarted(track));
         }
     }
 
@@ -237,6 +663,7 @@ impl Player {
     fn pause(&self) {
         assert!(self.streamer.state() != StreamState::Paused);
         self.streamer.pause();
+        emit(&self.subscribers, PlayerEvent::Paused);
     }
 
     /// Stop playback of the current track.
@@ -244,7 +671,8 @@ impl Player {
     /// Will do nothing if player is already stopped.
     fn stop(&self) {
         if self.is_playing() {
-            self.streamer.stop()
+            self.streamer.stop();
+            emit(&self.subscribers, PlayerEvent::Stopped);
         }
     }
 
@@ -253,16 +681,27 @@ impl Player {
     /// If there are no more items, the player will stop, unless `is_looping`
     /// is set to true, in which case it will start again from the beginning.
     fn next_track(&mut self) {
+        let finished = self.current_track();
         self.stop();
 
-        if self.play_queue.next().is_none() {
+        let has_next = self.play_queue.lock().unwrap().next().is_some();
+
+        if !has_next {
             if self.is_looping {
-                self.play_queue.reset();
+                self.play_queue.lock().unwrap().reset();
             } else {
+                if let Some(track) = finished {
+                    emit(&self.subscribers, PlayerEvent::TrackFinished(track));
+                }
+                emit(&self.subscribers, PlayerEvent::EndOfQueue);
                 return;
             }
         }
 
+        if let Some(track) = finished {
+            emit(&self.subscribers, PlayerEvent::TrackFinished(track));
+        }
+
         self.play();
     }
 
@@ -271,8 +710,14 @@ impl Player {
     /// If there are no more previous items the track will be started
     /// from the beginning.
     fn previous_track(&mut self) {
+        let finished = self.current_track();
         self.stop();
-        self.play_queue.previous();
+        self.play_queue.lock().unwrap().previous();
+
+        if let Some(track) = finished {
+            emit(&self.subscribers, PlayerEvent::TrackFinished(track));
+        }
+
         self.play();
     }
 }
@@ -0,0 +1,68 @@
+/// A simple forward/backward cursor over a list of queued items, tracking
+/// the item that is currently selected for playback.
+#[derive(Clone)]
+pub struct PlayQueue<T> {
+    items: Vec<T>,
+    current: Option<usize>,
+}
+
+impl<T: Clone> PlayQueue<T> {
+    /// Create an empty play queue with nothing queued.
+    pub fn new() -> PlayQueue<T> {
+        PlayQueue {
+            items: Vec::new(),
+            current: None,
+        }
+    }
+
+    /// Append an item to the end of the queue.
+    pub fn append(&mut self, item: &T) {
+        self.items.push(item.clone());
+
+        if self.current.is_none() {
+            self.current = Some(0);
+        }
+    }
+
+    /// Return the currently selected item, if any.
+    pub fn current(&self) -> Option<&T> {
+        self.current.and_then(|i| self.items.get(i))
+    }
+
+    /// Advance to the next item in the queue.
+    ///
+    /// Returns `None` without moving the cursor if the queue is already
+    /// at its last item.
+    pub fn next(&mut self) -> Option<&T> {
+        let next_index = self.current.map_or(0, |i| i + 1);
+
+        if next_index >= self.items.len() {
+            return None;
+        }
+
+        self.current = Some(next_index);
+        self.current()
+    }
+
+    /// Look at the item after the current one without moving the cursor.
+    pub fn peek_next(&self) -> Option<&T> {
+        let next_index = self.current.map_or(0, |i| i + 1);
+        self.items.get(next_index)
+    }
+
+    /// Move to the previous item in the queue, stopping at the first.
+    pub fn previous(&mut self) -> Option<&T> {
+        let prev_index = match self.current {
+            Some(0) | None => 0,
+            Some(i) => i - 1,
+        };
+
+        self.current = Some(prev_index);
+        self.current()
+    }
+
+    /// Reset the cursor back to the first item in the queue.
+    pub fn reset(&mut self) {
+        self.current = if self.items.is_empty() { None } else { Some(0) };
+    }
+}